@@ -0,0 +1,233 @@
+// Rust Lesson 13: A Compact Binary Wire Format for Recordings
+// -----------------------------------------------------------------------
+// JSON keystroke timelines are verbose: a browser extension posting a
+// long session sends many bytes per event. This module defines a compact
+// binary encoding and a base64 wrapper so those uploads shrink
+// dramatically while decoding back into the same `KeystrokeEvent` stream
+// the JSON path produces.
+//
+// Each record is: one kind byte, a LEB128 varint delta from the previous
+// event's timestamp, and a LEB128 varint inserted length. Delta-encoding
+// the timestamps keeps them small and monotonic.
+//
+// Validation is strict on purpose. The analyzer's metrics are only
+// trustworthy on intact input, so a malformed payload is rejected with a
+// clear `json_error` rather than silently truncated.
+
+use crate::{analyze_timeline, json_error, KeystrokeEvent};
+
+// Kind byte values, matching the string kinds used by the JSON path.
+const KIND_INSERT: u8 = 0;
+const KIND_BACKSPACE: u8 = 1;
+const KIND_DELETE: u8 = 2;
+
+#[wasm_bindgen::prelude::wasm_bindgen]
+pub fn analyze_editing_pattern_b64(encoded: &str) -> String {
+    let bytes = match decode_base64(encoded) {
+        Ok(bytes) => bytes,
+        Err(Base64Error::Padding) => {
+            return json_error("invalid base64 padding");
+        }
+        Err(Base64Error::InvalidChar) => {
+            return json_error("invalid base64 input");
+        }
+    };
+
+    match decode_records(&bytes) {
+        Ok(events) => analyze_timeline(events),
+        Err(()) => json_error("Failed to decode keystroke records"),
+    }
+}
+
+enum Base64Error {
+    // Incorrect padding: a length that can't form whole bytes, a '=' in
+    // the middle of the stream, or trailing bits that aren't zero.
+    Padding,
+    InvalidChar,
+}
+
+// Map a base64 character to its 6-bit value.
+fn decode_char(c: u8) -> Option<u8> {
+    match c {
+        b'A'..=b'Z' => Some(c - b'A'),
+        b'a'..=b'z' => Some(c - b'a' + 26),
+        b'0'..=b'9' => Some(c - b'0' + 52),
+        b'+' => Some(62),
+        b'/' => Some(63),
+        _ => None,
+    }
+}
+
+fn decode_base64(input: &str) -> Result<Vec<u8>, Base64Error> {
+    let bytes = input.as_bytes();
+
+    // Standard padded base64 always comes in groups of four. A length
+    // that isn't a multiple of four leaves trailing bits that cannot form
+    // a whole byte.
+    if !bytes.len().is_multiple_of(4) {
+        return Err(Base64Error::Padding);
+    }
+
+    let groups = bytes.len() / 4;
+    let mut out = Vec::with_capacity(groups * 3);
+
+    for g in 0..groups {
+        let base = g * 4;
+        let mut quad = [0u8; 4];
+        let mut pad = 0usize;
+
+        for (j, slot) in quad.iter_mut().enumerate() {
+            let c = bytes[base + j];
+            if c == b'=' {
+                pad += 1;
+                *slot = 0;
+            } else {
+                // A data character after a pad character means padding in
+                // the middle of a group.
+                if pad > 0 {
+                    return Err(Base64Error::Padding);
+                }
+                match decode_char(c) {
+                    Some(v) => *slot = v,
+                    None => return Err(Base64Error::InvalidChar),
+                }
+            }
+        }
+
+        // Padding may only appear in the final group, and at most twice.
+        if pad > 0 && g != groups - 1 {
+            return Err(Base64Error::Padding);
+        }
+        if pad > 2 {
+            return Err(Base64Error::Padding);
+        }
+
+        let n = (quad[0] as u32) << 18
+            | (quad[1] as u32) << 12
+            | (quad[2] as u32) << 6
+            | (quad[3] as u32);
+
+        match pad {
+            0 => {
+                out.push((n >> 16) as u8);
+                out.push((n >> 8) as u8);
+                out.push(n as u8);
+            }
+            1 => {
+                // Three data chars decode to two bytes; the low two bits
+                // of the third char are discarded and must be zero.
+                if quad[2] & 0x03 != 0 {
+                    return Err(Base64Error::Padding);
+                }
+                out.push((n >> 16) as u8);
+                out.push((n >> 8) as u8);
+            }
+            2 => {
+                // Two data chars decode to one byte; the low four bits of
+                // the second char are discarded and must be zero.
+                if quad[1] & 0x0f != 0 {
+                    return Err(Base64Error::Padding);
+                }
+                out.push((n >> 16) as u8);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    Ok(out)
+}
+
+// Read a LEB128 unsigned varint, returning the value and how many bytes
+// it consumed.
+fn read_varint(data: &[u8]) -> Result<(u64, usize), ()> {
+    let mut value = 0u64;
+    let mut shift = 0u32;
+    for (i, &byte) in data.iter().enumerate() {
+        if shift >= 64 {
+            return Err(());
+        }
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok((value, i + 1));
+        }
+        shift += 7;
+    }
+    // Ran off the end mid-varint.
+    Err(())
+}
+
+fn decode_records(data: &[u8]) -> Result<Vec<KeystrokeEvent>, ()> {
+    let mut events = Vec::new();
+    let mut i = 0usize;
+    let mut timestamp_ms = 0u64;
+
+    while i < data.len() {
+        let kind = match data[i] {
+            KIND_INSERT => "insert",
+            KIND_BACKSPACE => "backspace",
+            KIND_DELETE => "delete",
+            _ => return Err(()),
+        };
+        i += 1;
+
+        let (delta, advanced) = read_varint(&data[i..])?;
+        i += advanced;
+        let (inserted_len, advanced) = read_varint(&data[i..])?;
+        i += advanced;
+
+        timestamp_ms = timestamp_ms.saturating_add(delta);
+        events.push(KeystrokeEvent {
+            kind: kind.to_string(),
+            key: None,
+            timestamp_ms,
+            inserted_len: inserted_len as u32,
+        });
+    }
+
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip_decodes_records() {
+        // Two insertions: one char at t=0, then 500 chars 5ms later — the
+        // same paste shape the JSON path flags as AI. Records:
+        //   [0, 0, 1]  kind=insert delta=0 len=1
+        //   [0, 5, 500] kind=insert delta=5 len=500 (500 = 0xF4,0x03 varint)
+        let raw = [0u8, 0, 1, 0, 5, 0xF4, 0x03];
+        let events = decode_records(&raw).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].timestamp_ms, 5);
+        assert_eq!(events[1].inserted_len, 500);
+    }
+
+    #[test]
+    fn test_rejects_bad_length() {
+        // Five characters can never be valid padded base64.
+        assert!(matches!(decode_base64("AAAAA"), Err(Base64Error::Padding)));
+    }
+
+    #[test]
+    fn test_rejects_midstream_padding() {
+        assert!(matches!(decode_base64("AB=C"), Err(Base64Error::Padding)));
+    }
+
+    #[test]
+    fn test_rejects_nonzero_trailing_bits() {
+        // "AB==" keeps only the first byte; the second char's low four
+        // bits must be zero. 'Q' (value 16) has a clear low nibble so it
+        // is valid, while 'B' (value 1) leaves a stray bit.
+        assert!(matches!(decode_base64("AB=="), Err(Base64Error::Padding)));
+        assert!(decode_base64("AQ==").is_ok());
+    }
+
+    #[test]
+    fn test_padding_error_surfaces_as_json() {
+        let result = analyze_editing_pattern_b64("AAAAA");
+        assert!(result.contains("invalid base64 padding"));
+        assert!(result.contains("\"is_error\":true"));
+    }
+}
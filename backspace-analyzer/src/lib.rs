@@ -7,6 +7,17 @@
 use wasm_bindgen::prelude::*;
 use serde::{Serialize, Deserialize};
 
+// The interactive Language Server Protocol front-end lives in its own
+// module; the WASM entry points below remain the one-shot analysis path.
+pub mod lsp;
+
+// SIMD JSON fast path, only compiled when the `simd` feature is enabled.
+#[cfg(feature = "simd")]
+mod simd;
+
+// Compact base64 binary encoding for keystroke timelines.
+mod binfmt;
+
 // Rust Lesson 2: Creating Custom Types
 // -----------------------------------------------------------------------
 // We use #[derive] to automatically implement traits for our structs
@@ -27,6 +38,12 @@ pub struct EditingStats {
 // We use impl to define methods that belong to a struct
 // &self refers to the instance the method is called on
 
+impl Default for EditingStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl EditingStats {
     pub fn new() -> Self {
         EditingStats {
@@ -81,8 +98,8 @@ pub fn analyze_editing_pattern(stats_json: &str) -> String {
         Err(_) => return json_error("Failed to parse editing stats"),
     };
     
-    let analysis = perform_analysis(&stats);
-    
+    let analysis = perform_analysis(&stats, None);
+
     // Convert the analysis result back to JSON
     match serde_json::to_string(&analysis) {
         Ok(json) => json,
@@ -90,6 +107,179 @@ pub fn analyze_editing_pattern(stats_json: &str) -> String {
     }
 }
 
+// Rust Lesson 11: Faster Ingestion of Large Payloads
+// -----------------------------------------------------------------------
+// `analyze_editing_pattern` takes a `&str` and leans on `serde_json`.
+// For clients uploading multi-megabyte recordings, the same analysis is
+// available over a mutable byte buffer so the SIMD parser in `simd.rs`
+// can parse in place (it minifies the buffer in situ, then scans it). The
+// fast path is only taken when the `simd`
+// feature is compiled in and the CPU has AVX2; on any parse error, or
+// without those, we fall back to the identical `serde_json` path, so
+// callers see the same output JSON either way.
+
+#[wasm_bindgen]
+pub fn analyze_editing_pattern_bytes(buf: &mut [u8]) -> String {
+    let stats = match parse_stats_bytes(buf) {
+        Ok(stats) => stats,
+        Err(_) => return json_error("Failed to parse editing stats"),
+    };
+    serialize_analysis(&perform_analysis(&stats, None))
+}
+
+fn parse_stats_bytes(buf: &mut [u8]) -> Result<EditingStats, ()> {
+    #[cfg(feature = "simd")]
+    {
+        if simd::avx2_available() {
+            if let Ok(stats) = simd::parse_editing_stats(buf) {
+                return Ok(stats);
+            }
+        }
+    }
+    serde_json::from_slice(buf).map_err(|_| ())
+}
+
+// Rust Lesson 12: Ingesting Raw Keystroke Timelines
+// -----------------------------------------------------------------------
+// Aggregate `EditingStats` throws away timing, which is the strongest
+// human/AI signal. A `KeystrokeEvent` stream keeps the timestamp of every
+// edit so we can measure rhythm and spot bulk pastes.
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct KeystrokeEvent {
+    pub kind: String,
+    pub key: Option<String>,
+    pub timestamp_ms: u64,
+    pub inserted_len: u32,
+}
+
+// Timing signals derived from a keystroke timeline. `rhythm_regularity`
+// is the coefficient of variation of inter-key intervals: humans pause
+// and burst (high CV), while machine paste is near-uniform (CV ~ 0).
+struct TimingFeatures {
+    interval_mean_ms: f64,
+    rhythm_regularity: f64,
+    pasted_char_ratio: f64,
+}
+
+// A single insertion of more than one character, or a run of this many
+// consecutive rapid insertions, is treated as a paste burst.
+const PASTE_RUN_LEN: usize = 8;
+const PASTE_INTERVAL_MS: f64 = 10.0;
+
+#[wasm_bindgen]
+pub fn analyze_keystroke_timeline(events_json: &str) -> String {
+    let events: Vec<KeystrokeEvent> = match serde_json::from_str(events_json) {
+        Ok(events) => events,
+        Err(_) => return json_error("Failed to parse keystroke events"),
+    };
+
+    analyze_timeline(events)
+}
+
+// Run the timing-aware analysis over an already-decoded timeline. Shared
+// by the JSON and compact-binary entry points.
+fn analyze_timeline(mut events: Vec<KeystrokeEvent>) -> String {
+    // Sort by timestamp so out-of-order delivery doesn't corrupt the
+    // interval sequence.
+    events.sort_by_key(|e| e.timestamp_ms);
+
+    let stats = stats_from_events(&events);
+
+    // Fewer than two events carries no timing information; fall back to
+    // the aggregate path.
+    if events.len() < 2 {
+        return serialize_analysis(&perform_analysis(&stats, None));
+    }
+
+    let timing = timing_features(&events, &stats);
+    serialize_analysis(&perform_analysis(&stats, Some(&timing)))
+}
+
+fn serialize_analysis(analysis: &AnalysisResult) -> String {
+    match serde_json::to_string(analysis) {
+        Ok(json) => json,
+        Err(_) => json_error("Failed to serialize analysis results"),
+    }
+}
+
+// Rebuild the aggregate `EditingStats` from the raw stream so the same
+// ratio heuristics still apply.
+fn stats_from_events(events: &[KeystrokeEvent]) -> EditingStats {
+    let mut stats = EditingStats::new();
+    stats.total_keystrokes = events.len() as u32;
+    for event in events {
+        match event.kind.as_str() {
+            "backspace" => stats.backspace_count += 1,
+            "delete" => stats.delete_count += 1,
+            _ => stats.characters_typed += event.inserted_len,
+        }
+    }
+    if let (Some(first), Some(last)) = (events.first(), events.last()) {
+        stats.edit_duration_ms = last.timestamp_ms.saturating_sub(first.timestamp_ms);
+    }
+    stats
+}
+
+fn timing_features(events: &[KeystrokeEvent], stats: &EditingStats) -> TimingFeatures {
+    // Inter-key intervals, clamping non-monotonic jumps to 0.
+    let mut intervals = Vec::with_capacity(events.len() - 1);
+    for pair in events.windows(2) {
+        let delta = pair[1].timestamp_ms as i64 - pair[0].timestamp_ms as i64;
+        intervals.push(delta.max(0) as f64);
+    }
+
+    let mean = intervals.iter().sum::<f64>() / intervals.len() as f64;
+    let rhythm_regularity = if mean > 0.0 {
+        let variance =
+            intervals.iter().map(|d| (d - mean).powi(2)).sum::<f64>() / intervals.len() as f64;
+        variance.sqrt() / mean
+    } else {
+        0.0
+    };
+
+    // Count characters introduced by paste bursts: any multi-character
+    // insertion, or a run of >= PASTE_RUN_LEN consecutive single-character
+    // insertions whose intervals are all under PASTE_INTERVAL_MS.
+    let mut pasted_chars = 0u32;
+    let mut run_start = 0usize;
+    for i in 0..events.len() {
+        if events[i].inserted_len > 1 {
+            pasted_chars += events[i].inserted_len;
+        }
+
+        let is_rapid_single = events[i].inserted_len == 1
+            && i > 0
+            && ((events[i].timestamp_ms as i64 - events[i - 1].timestamp_ms as i64).max(0) as f64)
+                < PASTE_INTERVAL_MS;
+        if !is_rapid_single {
+            // A run can only begin at a single-character insertion.
+            run_start = if events[i].inserted_len == 1 { i } else { i + 1 };
+        } else if i - run_start + 1 >= PASTE_RUN_LEN {
+            // Entering/extending a qualifying run: count this event, and
+            // back-fill the earlier members on the step that first reached
+            // the threshold.
+            if i - run_start + 1 == PASTE_RUN_LEN {
+                pasted_chars += PASTE_RUN_LEN as u32;
+            } else {
+                pasted_chars += 1;
+            }
+        }
+    }
+
+    let pasted_char_ratio = if stats.characters_typed > 0 {
+        (pasted_chars.min(stats.characters_typed)) as f64 / stats.characters_typed as f64
+    } else {
+        0.0
+    };
+
+    TimingFeatures {
+        interval_mean_ms: mean,
+        rhythm_regularity,
+        pasted_char_ratio,
+    }
+}
+
 // Rust Lesson 5: Error Handling and JSON Utilities
 // -----------------------------------------------------------------------
 // Creating helper functions to handle errors gracefully
@@ -113,6 +303,15 @@ struct AnalysisResult {
     backspace_ratio: f64,
     typing_speed: f64,
     metrics: EditingMetrics,
+    // Timing-derived signals, only present when the analysis was fed a
+    // keystroke timeline (see `analyze_keystroke_timeline`). Omitted from
+    // the aggregate path so existing callers see an unchanged shape.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rhythm_mean_ms: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rhythm_regularity: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pasted_char_ratio: Option<f64>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -122,7 +321,7 @@ struct EditingMetrics {
     correction_patterns: Vec<String>,
 }
 
-fn perform_analysis(stats: &EditingStats) -> AnalysisResult {
+fn perform_analysis(stats: &EditingStats, timing: Option<&TimingFeatures>) -> AnalysisResult {
     // Calculate metrics
     let backspace_ratio = stats.backspace_ratio();
     let typing_speed = stats.typing_speed();
@@ -159,6 +358,17 @@ fn perform_analysis(stats: &EditingStats) -> AnalysisResult {
         prediction = "Human";
     }
     
+    // When a keystroke timeline is available, large pasted spans with no
+    // corrections are the clearest AI tell — machine output arrives in
+    // bulk and is never backspaced. This overrides a plausible-looking
+    // typing speed, which paste can easily fake.
+    if let Some(t) = timing {
+        if t.pasted_char_ratio > 0.5 && backspace_ratio < 0.05 {
+            confidence = f64::max(confidence, 0.9);
+            prediction = "AI";
+        }
+    }
+
     // Cap confidence between 0.5 and 0.95
     confidence = f64::min(confidence, 0.95).max(0.5);
     
@@ -191,7 +401,10 @@ fn perform_analysis(stats: &EditingStats) -> AnalysisResult {
             correction_frequency: stats.correction_ratio(),
             character_efficiency,
             correction_patterns: patterns,
-        }
+        },
+        rhythm_mean_ms: timing.map(|t| t.interval_mean_ms),
+        rhythm_regularity: timing.map(|t| t.rhythm_regularity),
+        pasted_char_ratio: timing.map(|t| t.pasted_char_ratio),
     }
 }
 
@@ -237,4 +450,47 @@ mod tests {
         assert_eq!(analysis.prediction, "AI");
         assert!(analysis.confidence > 0.7);
     }
+
+    #[test]
+    fn test_keystroke_timeline_flags_paste_as_ai() {
+        // A single large insertion with no corrections: the classic paste.
+        let events = vec![
+            KeystrokeEvent {
+                kind: "insert".to_string(),
+                key: None,
+                timestamp_ms: 0,
+                inserted_len: 1,
+            },
+            KeystrokeEvent {
+                kind: "insert".to_string(),
+                key: None,
+                timestamp_ms: 5,
+                inserted_len: 500,
+            },
+        ];
+
+        let result = analyze_keystroke_timeline(&serde_json::to_string(&events).unwrap());
+        let analysis: AnalysisResult = serde_json::from_str(&result).unwrap();
+
+        assert_eq!(analysis.prediction, "AI");
+        assert!(analysis.confidence >= 0.9);
+        assert!(analysis.pasted_char_ratio.unwrap() > 0.9);
+    }
+
+    #[test]
+    fn test_keystroke_timeline_single_event_uses_aggregate() {
+        let events = vec![KeystrokeEvent {
+            kind: "insert".to_string(),
+            key: Some("a".to_string()),
+            timestamp_ms: 0,
+            inserted_len: 1,
+        }];
+
+        let result = analyze_keystroke_timeline(&serde_json::to_string(&events).unwrap());
+        let analysis: AnalysisResult = serde_json::from_str(&result).unwrap();
+
+        // No timing information, so the timing fields are omitted entirely.
+        assert!(analysis.rhythm_regularity.is_none());
+        assert!(analysis.pasted_char_ratio.is_none());
+    }
 }
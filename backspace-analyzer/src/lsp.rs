@@ -0,0 +1,278 @@
+// Rust Lesson 8: A Language Server Protocol Front-End
+// -----------------------------------------------------------------------
+// The WASM `analyze_editing_pattern` entry point is one-shot: an editor
+// collects an aggregate `EditingStats` blob and asks for a verdict once.
+// This module turns the same analysis into an interactive service. It
+// speaks a small subset of the Language Server Protocol so that any
+// LSP-capable editor can stream edits as they happen (`textDocument/
+// didChange`), keep a live `EditingStats` session per document, and
+// receive the current verdict back as diagnostics.
+//
+// We keep the transport out of this module on purpose: the functions
+// here turn an incoming notification into the JSON-RPC messages that
+// should be written back to the client, so the same code works whether
+// the bytes travel over stdio, a socket, or a WASM message channel.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{perform_analysis, EditingStats};
+
+// LSP content changes can be ranged (an incremental edit) or a full
+// document replacement. We only need the text and, for deletions, how
+// much was removed, so the range is represented by the count of removed
+// UTF-16 code units the protocol reports in `rangeLength`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ContentChange {
+    pub text: String,
+    #[serde(rename = "rangeLength", default)]
+    pub range_length: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TextDocumentIdentifier {
+    pub uri: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DidChangeParams {
+    #[serde(rename = "textDocument")]
+    pub text_document: TextDocumentIdentifier,
+    #[serde(rename = "contentChanges")]
+    pub content_changes: Vec<ContentChange>,
+}
+
+// A diagnostic is the vehicle we reuse to surface the authorship verdict
+// in the editor's problems view.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Diagnostic {
+    pub message: String,
+    pub severity: u8,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PublishDiagnosticsParams {
+    pub uri: String,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+// `$/progress` payload mirroring rust-analyzer's indexing stream so an
+// editor can render a live status bar while a long recording is ingested.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ProgressParams {
+    pub token: String,
+    pub percentage: u8,
+    pub message: String,
+}
+
+// A single JSON-RPC notification ready to be written to the client.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Notification {
+    pub jsonrpc: String,
+    pub method: String,
+    pub params: serde_json::Value,
+}
+
+impl Notification {
+    fn new(method: &str, params: serde_json::Value) -> Self {
+        Notification {
+            jsonrpc: "2.0".to_string(),
+            method: method.to_string(),
+            params,
+        }
+    }
+}
+
+// How often, in keystrokes, to emit a `$/progress` tick. Mirrors the
+// coarse-grained indexing updates editors expect — one per chunk rather
+// than one per event.
+const PROGRESS_CHUNK: u32 = 320;
+
+// Rust Lesson 9: Holding Per-Document Session State
+// -----------------------------------------------------------------------
+// Unlike the stateless WASM path, the server has to remember a running
+// `EditingStats` for every open document and fold each change into it.
+
+#[derive(Default)]
+pub struct LspServer {
+    sessions: HashMap<String, EditingStats>,
+}
+
+impl LspServer {
+    pub fn new() -> Self {
+        LspServer {
+            sessions: HashMap::new(),
+        }
+    }
+
+    // Fold a single content change into the session, mapping the change
+    // kind to the matching counter. An empty replacement text that removed
+    // characters is a correction (backspace/delete); anything that inserts
+    // text counts as keystrokes plus typed characters.
+    fn apply_change(stats: &mut EditingStats, change: &ContentChange) {
+        stats.total_keystrokes += 1;
+        if change.text.is_empty() {
+            // Pure removal. We treat a single removed unit as a backspace
+            // and a larger ranged removal as a forward delete, which keeps
+            // the two counters meaningful for the ratio heuristics.
+            if change.range_length <= 1 {
+                stats.backspace_count += 1;
+            } else {
+                stats.delete_count += 1;
+            }
+        } else {
+            let inserted = change.text.chars().count() as u32;
+            stats.characters_typed += inserted;
+        }
+    }
+
+    // Handle `textDocument/didChange`: update the session, re-run the
+    // analysis, and produce the notifications the client should receive —
+    // a `$/progress` tick for every chunk of keystrokes, then the verdict
+    // as `textDocument/publishDiagnostics`.
+    pub fn handle_did_change(&mut self, params: &DidChangeParams) -> Vec<Notification> {
+        let uri = params.text_document.uri.clone();
+        let stats = self.sessions.entry(uri.clone()).or_default();
+
+        let mut notifications = Vec::new();
+        for change in &params.content_changes {
+            Self::apply_change(stats, change);
+            if stats.total_keystrokes.is_multiple_of(PROGRESS_CHUNK) {
+                notifications.push(progress_notification(&uri, stats.total_keystrokes));
+            }
+        }
+
+        let analysis = perform_analysis(stats, None);
+        let message = format!(
+            "{} ({:.0}% confidence)",
+            analysis.prediction,
+            analysis.confidence * 100.0
+        );
+        let diagnostics = PublishDiagnosticsParams {
+            uri,
+            diagnostics: vec![Diagnostic {
+                message,
+                // Information severity — a verdict, not an error.
+                severity: 3,
+            }],
+        };
+        notifications.push(Notification::new(
+            "textDocument/publishDiagnostics",
+            serde_json::to_value(diagnostics).unwrap_or(serde_json::Value::Null),
+        ));
+        notifications
+    }
+}
+
+// Rust Lesson 10: Wiring the Server to a Transport
+// -----------------------------------------------------------------------
+// `LspServer` is transport-agnostic; the WASM-facing handle below is the
+// entry point an editor's JS glue actually holds. It keeps one server
+// instance alive across calls and turns each `textDocument/didChange`
+// params blob into the JSON array of notifications to write back.
+#[wasm_bindgen::prelude::wasm_bindgen]
+pub struct LspSession {
+    inner: LspServer,
+}
+
+impl Default for LspSession {
+    fn default() -> Self {
+        LspSession::new()
+    }
+}
+
+#[wasm_bindgen::prelude::wasm_bindgen]
+impl LspSession {
+    #[wasm_bindgen::prelude::wasm_bindgen(constructor)]
+    pub fn new() -> LspSession {
+        LspSession {
+            inner: LspServer::new(),
+        }
+    }
+
+    pub fn did_change(&mut self, params_json: &str) -> String {
+        let params: DidChangeParams = match serde_json::from_str(params_json) {
+            Ok(params) => params,
+            Err(_) => return crate::json_error("Failed to parse didChange params"),
+        };
+        let notifications = self.inner.handle_did_change(&params);
+        serde_json::to_string(&notifications)
+            .unwrap_or_else(|_| crate::json_error("Failed to serialize notifications"))
+    }
+}
+
+// Build a `$/progress` notification reporting how far through a nominal
+// 1000-keystroke window we are, matching the indexing-progress register
+// ("analyzing 320/1000 keystrokes").
+fn progress_notification(uri: &str, keystrokes: u32) -> Notification {
+    const WINDOW: u32 = 1000;
+    let percentage = ((keystrokes.min(WINDOW) as f64 / WINDOW as f64) * 100.0) as u8;
+    let params = ProgressParams {
+        token: uri.to_string(),
+        percentage,
+        message: format!("analyzing {}/{} keystrokes", keystrokes.min(WINDOW), WINDOW),
+    };
+    Notification::new(
+        "$/progress",
+        serde_json::to_value(params).unwrap_or(serde_json::Value::Null),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn insertion(text: &str) -> ContentChange {
+        ContentChange {
+            text: text.to_string(),
+            range_length: 0,
+        }
+    }
+
+    fn deletion(range_length: u32) -> ContentChange {
+        ContentChange {
+            text: String::new(),
+            range_length,
+        }
+    }
+
+    #[test]
+    fn test_did_change_accumulates_session() {
+        let mut server = LspServer::new();
+        let params = DidChangeParams {
+            text_document: TextDocumentIdentifier {
+                uri: "file:///doc.rs".to_string(),
+            },
+            content_changes: vec![insertion("hello"), deletion(1), insertion("x")],
+        };
+
+        let notifications = server.handle_did_change(&params);
+
+        // Last notification is always the published verdict.
+        let last = notifications.last().unwrap();
+        assert_eq!(last.method, "textDocument/publishDiagnostics");
+
+        let stats = &server.sessions["file:///doc.rs"];
+        assert_eq!(stats.total_keystrokes, 3);
+        assert_eq!(stats.backspace_count, 1);
+        assert_eq!(stats.characters_typed, 6);
+    }
+
+    #[test]
+    fn test_progress_emitted_every_chunk() {
+        let mut server = LspServer::new();
+        let params = DidChangeParams {
+            text_document: TextDocumentIdentifier {
+                uri: "file:///big.rs".to_string(),
+            },
+            content_changes: (0..PROGRESS_CHUNK).map(|_| insertion("a")).collect(),
+        };
+
+        let notifications = server.handle_did_change(&params);
+        assert!(notifications
+            .iter()
+            .any(|n| n.method == "$/progress"
+                && n.params["message"] == format!("analyzing {}/1000 keystrokes", PROGRESS_CHUNK)));
+    }
+}
@@ -0,0 +1,289 @@
+// Rust Lesson 11: A SIMD Fast Path for the Parsing Layer
+// -----------------------------------------------------------------------
+// Clients that upload multi-megabyte session logs spend most of their
+// time in JSON parsing, not in the analysis itself. This module is a
+// small, simdjson-style parser that works directly on the caller's byte
+// slice: the aggregate `EditingStats` object is flat and all-integer, so
+// a single scan over the bytes reconstructs it without the allocations
+// `serde_json::from_str` makes.
+//
+// The module is only compiled under the `simd` feature, and the entry
+// point in `lib.rs` only reaches for it when AVX2 is actually present at
+// run time — otherwise it falls back to the `serde_json` path. The
+// analysis and output JSON are untouched; this is purely ingestion.
+
+use crate::EditingStats;
+
+// True when the current CPU can run the AVX2 whitespace scanner. On
+// non-x86 targets (including WASM) there is no AVX2, so callers take the
+// scalar fallback.
+pub fn avx2_available() -> bool {
+    #[cfg(target_arch = "x86_64")]
+    {
+        std::arch::is_x86_feature_detected!("avx2")
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        false
+    }
+}
+
+// Parse a flat `{"key": <uint>, ...}` object in place. The buffer is first
+// minified in situ — structural whitespace is collapsed to the front of
+// the same allocation, the way simdjson rewrites its input — and the scan
+// then runs over the compacted prefix. Returns `Err` on anything it
+// doesn't recognise so the caller can fall back to the strict
+// `serde_json` parser rather than guessing.
+pub fn parse_editing_stats(buf: &mut [u8]) -> Result<EditingStats, ()> {
+    let avx2 = avx2_available();
+    let len = minify_ws(buf, avx2);
+    let buf = &buf[..len];
+    let n = buf.len();
+    let mut stats = EditingStats::new();
+    let mut i = 0usize;
+
+    // Track which of the five required keys we've seen so a payload that
+    // omits any is rejected, matching `serde_json` (which has no
+    // `#[serde(default)]` on `EditingStats`).
+    let mut seen = 0u8;
+
+    if i >= n || buf[i] != b'{' {
+        return Err(());
+    }
+    i += 1;
+
+    loop {
+        if i < n && buf[i] == b'}' {
+            break;
+        }
+
+        // Key string.
+        if i >= n || buf[i] != b'"' {
+            return Err(());
+        }
+        i += 1;
+        let key_start = i;
+        while i < n && buf[i] != b'"' {
+            i += 1;
+        }
+        if i >= n {
+            return Err(());
+        }
+        let key = &buf[key_start..i];
+        i += 1; // closing quote
+
+        if i >= n || buf[i] != b':' {
+            return Err(());
+        }
+        i += 1;
+
+        // Unsigned integer value.
+        let num_start = i;
+        while i < n && buf[i].is_ascii_digit() {
+            i += 1;
+        }
+        // JSON forbids leading zeros, so `serde_json` rejects them too.
+        if i == num_start || (buf[num_start] == b'0' && i - num_start > 1) {
+            return Err(());
+        }
+        let value: u64 = std::str::from_utf8(&buf[num_start..i])
+            .map_err(|_| ())?
+            .parse()
+            .map_err(|_| ())?;
+
+        // The `u32` fields must fit; `serde_json` rejects out-of-range
+        // integers, so range-check rather than silently truncating.
+        let as_u32 = || u32::try_from(value).map_err(|_| ());
+        let seen_before = seen;
+        match key {
+            b"total_keystrokes" => {
+                stats.total_keystrokes = as_u32()?;
+                seen |= 1 << 0;
+            }
+            b"backspace_count" => {
+                stats.backspace_count = as_u32()?;
+                seen |= 1 << 1;
+            }
+            b"delete_count" => {
+                stats.delete_count = as_u32()?;
+                seen |= 1 << 2;
+            }
+            b"characters_typed" => {
+                stats.characters_typed = as_u32()?;
+                seen |= 1 << 3;
+            }
+            b"edit_duration_ms" => {
+                stats.edit_duration_ms = value;
+                seen |= 1 << 4;
+            }
+            // An unexpected key means the payload isn't the shape we
+            // fast-parse; defer to the safe path.
+            _ => return Err(()),
+        }
+        // A repeated key sets no new bit; `serde_json` rejects duplicates.
+        if seen == seen_before {
+            return Err(());
+        }
+
+        // Members are separated by exactly one comma; a missing comma, or a
+        // comma right before '}', is malformed JSON.
+        if i < n && buf[i] == b',' {
+            i += 1;
+            if i < n && buf[i] == b'}' {
+                return Err(());
+            }
+        } else if i >= n || buf[i] != b'}' {
+            return Err(());
+        }
+    }
+
+    // All five keys are required (no field defaults), and nothing may
+    // follow the closing brace — `serde_json::from_slice` rejects trailing
+    // data, so the fast path must too. Whitespace is already gone.
+    if seen != 0b1_1111 {
+        return Err(());
+    }
+    i += 1; // consume the closing '}'
+    if i != n {
+        return Err(());
+    }
+
+    Ok(stats)
+}
+
+// Collapse ASCII whitespace out of `buf` in place, returning the length of
+// the compacted prefix. The caller scans `&buf[..len]`. `skip_ws` uses the
+// AVX2 scanner when available, so the whitespace pass stays vectorised.
+// Whitespace next to a structural byte (`{`, `}`, `:`, `,`) or at either
+// end is dropped; a run between two other bytes (inside a number or a key)
+// is kept as one space so the scan rejects it, as `serde_json` would.
+//
+// The freed tail is overwritten with spaces rather than left stale, so the
+// buffer remains valid JSON (object followed by trailing whitespace) for
+// the `serde_json` fallback in `parse_stats_bytes` should the scan error.
+fn minify_ws(buf: &mut [u8], avx2: bool) -> usize {
+    let n = buf.len();
+    let mut read = 0usize;
+    let mut write = 0usize;
+    while read < n {
+        let before = read;
+        skip_ws(buf, &mut read, avx2);
+        if read > before {
+            let structural = |b: u8| matches!(b, b'{' | b'}' | b':' | b',');
+            if write > 0 && read < n && !structural(buf[write - 1]) && !structural(buf[read]) {
+                buf[write] = b' ';
+                write += 1;
+            }
+            continue;
+        }
+        buf[write] = buf[read];
+        write += 1;
+        read += 1;
+    }
+    for slot in &mut buf[write..] {
+        *slot = b' ';
+    }
+    write
+}
+
+// Advance `*i` past ASCII whitespace. When AVX2 is available we scan 32
+// bytes at a time; otherwise a scalar loop.
+fn skip_ws(buf: &[u8], i: &mut usize, avx2: bool) {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if avx2 {
+            // Safety: guarded by the run-time AVX2 feature detection above.
+            unsafe { skip_ws_avx2(buf, i) };
+            return;
+        }
+    }
+    let _ = avx2;
+    skip_ws_scalar(buf, i);
+}
+
+fn skip_ws_scalar(buf: &[u8], i: &mut usize) {
+    while *i < buf.len() && matches!(buf[*i], b' ' | b'\t' | b'\n' | b'\r') {
+        *i += 1;
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn skip_ws_avx2(buf: &[u8], i: &mut usize) {
+    use std::arch::x86_64::*;
+
+    let n = buf.len();
+    let spaces = _mm256_set1_epi8(b' ' as i8);
+    let tabs = _mm256_set1_epi8(b'\t' as i8);
+    let newlines = _mm256_set1_epi8(b'\n' as i8);
+    let returns = _mm256_set1_epi8(b'\r' as i8);
+
+    while *i + 32 <= n {
+        let chunk = _mm256_loadu_si256(buf.as_ptr().add(*i) as *const __m256i);
+        let is_ws = _mm256_or_si256(
+            _mm256_or_si256(
+                _mm256_cmpeq_epi8(chunk, spaces),
+                _mm256_cmpeq_epi8(chunk, tabs),
+            ),
+            _mm256_or_si256(
+                _mm256_cmpeq_epi8(chunk, newlines),
+                _mm256_cmpeq_epi8(chunk, returns),
+            ),
+        );
+        // Bit set per lane that is whitespace; the first zero bit is the
+        // first non-whitespace byte in the chunk.
+        let non_ws = !(_mm256_movemask_epi8(is_ws) as u32);
+        if non_ws != 0 {
+            *i += non_ws.trailing_zeros() as usize;
+            return;
+        }
+        *i += 32;
+    }
+    skip_ws_scalar(buf, i);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_matches_serde() {
+        let mut json = br#"{ "total_keystrokes": 1000, "backspace_count": 5,
+            "delete_count": 2, "characters_typed": 993, "edit_duration_ms": 60000 }"#
+            .to_vec();
+        let stats = parse_editing_stats(&mut json).unwrap();
+        assert_eq!(stats.total_keystrokes, 1000);
+        assert_eq!(stats.backspace_count, 5);
+        assert_eq!(stats.edit_duration_ms, 60000);
+    }
+
+    #[test]
+    fn test_unknown_key_bails_to_fallback() {
+        let mut json = br#"{"total_keystrokes": 1, "surprise": 2}"#.to_vec();
+        assert!(parse_editing_stats(&mut json).is_err());
+    }
+
+    #[test]
+    fn test_missing_field_bails_like_serde() {
+        // `EditingStats` has no field defaults, so a partial object must
+        // fall back rather than parse with zeros.
+        let mut json = br#"{"total_keystrokes": 5}"#.to_vec();
+        assert!(parse_editing_stats(&mut json).is_err());
+    }
+
+    #[test]
+    fn test_trailing_bytes_rejected() {
+        let mut json = br#"{ "total_keystrokes": 1, "backspace_count": 0,
+            "delete_count": 0, "characters_typed": 1, "edit_duration_ms": 0 }garbage"#
+            .to_vec();
+        assert!(parse_editing_stats(&mut json).is_err());
+    }
+
+    #[test]
+    fn test_u32_overflow_rejected() {
+        let mut json = br#"{ "total_keystrokes": 4294967296, "backspace_count": 0,
+            "delete_count": 0, "characters_typed": 0, "edit_duration_ms": 0 }"#
+            .to_vec();
+        assert!(parse_editing_stats(&mut json).is_err());
+    }
+}